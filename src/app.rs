@@ -0,0 +1,163 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::sync::mpsc::Receiver;
+use git2::{CheckoutBuilder, Repository};
+use tui::widgets::ListState;
+use crate::utility::{get_repository_active_branch, get_repository_branches, get_repository_tags, RefDetails};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum Selection {
+    REPOSITORIES,
+    TAGS,
+    BRANCHES,
+    BLAME
+}
+
+impl Display for Selection {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Selection::REPOSITORIES => write!(f, "Repositories"),
+            Selection::TAGS => write!(f, "Tags"),
+            Selection::BRANCHES => write!(f, "Branches"),
+            Selection::BLAME => write!(f, "Blame")
+        }
+    }
+}
+
+pub struct AlfredRepository {
+    pub folder_name: String,
+    pub path: PathBuf,
+    pub is_repository: bool,
+    pub active_branch_name: String
+}
+
+pub struct StatefulList<T> {
+    pub state: ListState,
+    pub items: Vec<T>
+}
+
+impl<T> StatefulList<T> {
+    pub fn with_items(items: Vec<T>) -> StatefulList<T> {
+        StatefulList {
+            state: ListState::default(),
+            items
+        }
+    }
+
+    pub fn unselect(&mut self) {
+        self.state.select(None);
+    }
+}
+
+pub struct App {
+    pub repositories: StatefulList<AlfredRepository>,
+    pub tags: StatefulList<String>,
+    pub branches: StatefulList<String>,
+    pub selection: Selection,
+    pub selected_repository_path: String,
+    pub repository: Option<Repository>,
+    pub active_branch_name: String,
+    pub filter: String,
+    pub filter_active: bool,
+    pub filtered_indices: Vec<usize>,
+    pub last_error: Option<String>,
+    pub fetch_progress: Option<f32>,
+    pub fetch_receiver: Option<Receiver<f32>>,
+    pub blame_cache: Vec<(Option<String>, String)>,
+    pub blame_cache_key: Option<(String, PathBuf)>,
+    pub ref_details_cache: RefDetails,
+    pub ref_details_cache_key: Option<String>
+}
+
+impl App {
+    pub fn change_selection(&mut self, selection: Selection) {
+        self.selection = selection;
+    }
+
+    pub fn next(&mut self) {
+        let len = self.filtered_indices.len();
+        if len == 0 {
+            return;
+        }
+
+        let state = self.active_state_mut();
+        let i = match state.selected() {
+            Some(i) if i + 1 < len => i + 1,
+            _ => 0
+        };
+        state.select(Some(i));
+    }
+
+    pub fn previous(&mut self) {
+        let len = self.filtered_indices.len();
+        if len == 0 {
+            return;
+        }
+
+        let state = self.active_state_mut();
+        let i = match state.selected() {
+            Some(0) | None => len - 1,
+            Some(i) => i - 1
+        };
+        state.select(Some(i));
+    }
+
+    // Drops the current highlight so a fresh fuzzy query doesn't keep pointing at a row
+    // that belonged to the previous, differently-ordered filtered view.
+    pub fn reset_selection(&mut self) {
+        self.active_state_mut().select(None);
+    }
+
+    fn active_state_mut(&mut self) -> &mut ListState {
+        match self.selection {
+            Selection::REPOSITORIES => &mut self.repositories.state,
+            Selection::TAGS => &mut self.tags.state,
+            Selection::BRANCHES => &mut self.branches.state,
+            Selection::BLAME => &mut self.repositories.state
+        }
+    }
+
+    pub fn on_tick(&mut self) {}
+
+    pub fn refresh_refs(&mut self) {
+        self.tags = StatefulList::with_items(get_repository_tags(&self.repository));
+        self.branches = StatefulList::with_items(get_repository_branches(&self.repository));
+    }
+
+    pub fn checkout_selected(&mut self) -> Result<(), String> {
+        let selected_name = match self.selection {
+            Selection::TAGS => self.selected_ref_name(&self.tags),
+            Selection::BRANCHES => self.selected_ref_name(&self.branches),
+            _ => None
+        };
+        let ref_name = selected_name.ok_or_else(|| "no branch or tag selected".to_string())?;
+
+        let repo = self.repository.as_ref().ok_or_else(|| "no repository selected".to_string())?;
+        let object = repo.revparse_single(&ref_name).map_err(|e| e.message().to_string())?;
+
+        let mut checkout_builder = CheckoutBuilder::new();
+        checkout_builder.safe();
+        repo.checkout_tree(&object, Some(&mut checkout_builder)).map_err(|e| e.message().to_string())?;
+
+        match self.selection {
+            Selection::BRANCHES => {
+                repo.set_head(&format!("refs/heads/{}", ref_name)).map_err(|e| e.message().to_string())?;
+            },
+            Selection::TAGS => {
+                let commit = object.peel_to_commit().map_err(|e| e.message().to_string())?;
+                repo.set_head_detached(commit.id()).map_err(|e| e.message().to_string())?;
+            },
+            _ => {}
+        }
+
+        self.active_branch_name = get_repository_active_branch(&self.repository);
+        Ok(())
+    }
+
+    fn selected_ref_name(&self, list: &StatefulList<String>) -> Option<String> {
+        let index = list.state.selected()?;
+        let real_index = *self.filtered_indices.get(index)?;
+        list.items.get(real_index).cloned()
+    }
+}
@@ -1,17 +1,20 @@
-use std::{io};
+use std::{io, thread};
 use std::fmt::Display;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::sync::mpsc::TryRecvError;
 use std::time::{Duration, Instant};
 use crossterm::event;
 use crossterm::event::{Event, KeyCode};
 use tui::backend::Backend;
 use tui::{Frame, Terminal};
-use tui::widgets::{Block, List, ListItem, Paragraph};
+use tui::widgets::{Block, Borders, Gauge, List, ListItem, Paragraph, Sparkline};
 use tui::layout::{Alignment, Constraint, Corner, Direction, Layout, Rect};
 use tui::style::{Color, Modifier, Style};
 use tui::text::{Span, Spans};
 use crate::{App};
 use crate::app::{AlfredRepository, Selection};
-use crate::utility::{convert_to_list_item, create_block, create_block_with_title};
+use crate::utility::{convert_to_list_item, create_block, create_block_with_title, fetch_origin_with_progress, fuzzy_filter, get_commit_activity, get_default_blame_file, get_file_blame, get_ref_details};
 
 pub fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
@@ -30,19 +33,73 @@ pub fn run_app<B: Backend>(
 
         if event::poll(timeout)? {
             if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Char('q') => return Ok(()),
-                    KeyCode::Left => app.repositories.unselect(),
-                    KeyCode::Down => app.next(),
-                    KeyCode::Up => app.previous(),
-                    KeyCode::Char('r') => app.change_selection(Selection::REPOSITORIES),
-                    KeyCode::Char('t') => app.change_selection(Selection::TAGS),
-                    KeyCode::Char('b') => app.change_selection(Selection::BRANCHES),
-                    _ => {}
+                if app.filter_active {
+                    match key.code {
+                        KeyCode::Esc => {
+                            app.filter_active = false;
+                            app.filter.clear();
+                            app.reset_selection();
+                        },
+                        KeyCode::Backspace => {
+                            app.filter.pop();
+                            app.reset_selection();
+                        },
+                        KeyCode::Char(c) => {
+                            app.filter.push(c);
+                            app.reset_selection();
+                        },
+                        KeyCode::Down => app.next(),
+                        KeyCode::Up => app.previous(),
+                        KeyCode::Enter => {
+                            if let Err(e) = app.checkout_selected() {
+                                app.last_error = Some(e);
+                            }
+                        },
+                        _ => {}
+                    }
+                } else {
+                    match key.code {
+                        KeyCode::Char('q') => return Ok(()),
+                        KeyCode::Left => app.repositories.unselect(),
+                        KeyCode::Down => app.next(),
+                        KeyCode::Up => app.previous(),
+                        KeyCode::Char('r') => app.change_selection(Selection::REPOSITORIES),
+                        KeyCode::Char('t') => app.change_selection(Selection::TAGS),
+                        KeyCode::Char('b') => app.change_selection(Selection::BRANCHES),
+                        KeyCode::Char('g') => app.change_selection(Selection::BLAME),
+                        KeyCode::Char('/') => app.filter_active = true,
+                        KeyCode::Enter => {
+                            if let Err(e) = app.checkout_selected() {
+                                app.last_error = Some(e);
+                            }
+                        },
+                        KeyCode::Char('f') => {
+                            let (tx, rx) = mpsc::channel();
+                            let repo_path = app.selected_repository_path.clone().into();
+                            thread::spawn(move || {
+                                let _ = fetch_origin_with_progress(repo_path, tx);
+                            });
+                            app.fetch_progress = Some(0.0);
+                            app.fetch_receiver = Some(rx);
+                        },
+                        _ => {}
+                    }
                 }
             }
         }
 
+        if let Some(rx) = &app.fetch_receiver {
+            match rx.try_recv() {
+                Ok(ratio) => app.fetch_progress = Some(ratio),
+                Err(TryRecvError::Disconnected) => {
+                    app.fetch_progress = None;
+                    app.fetch_receiver = None;
+                    app.refresh_refs();
+                },
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+
         if last_tick.elapsed() >= tick_rate {
             app.on_tick();
             last_tick = Instant::now()
@@ -53,33 +110,67 @@ pub fn run_app<B: Backend>(
 fn ui<'a, B: Backend>(f: &'a mut Frame<B>, app: &'a mut App) {
     let size = f.size();
 
-    // Big chunk divides screen for part and bottom info
+    // Big chunk divides screen for main part, activity sparkline and bottom info
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints(
             [
-                Constraint::Percentage(95),
+                Constraint::Percentage(85),
+                Constraint::Percentage(10),
                 Constraint::Percentage(5)
             ]
         )
         .split(size);
 
-    // Divides main part into two
+    // Divides main part into repositories, branches/tags and blame
     let main_chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints(
             [
-                Constraint::Percentage(50),
-                Constraint::Percentage(50)
+                Constraint::Percentage(40),
+                Constraint::Percentage(30),
+                Constraint::Percentage(30)
             ]
         )
         .split(chunks[0]);
 
-    // Files & folders
-    let items: Vec<ListItem> = app
-        .repositories
-        .items
+    // Display order of the active panel, filtered/fuzzy-sorted when a query is active
+    app.filtered_indices = match app.selection {
+        Selection::REPOSITORIES => {
+            if app.filter.is_empty() {
+                (0..app.repositories.items.len()).collect()
+            } else {
+                let names: Vec<String> = app.repositories.items.iter().map(|i| i.folder_name.clone()).collect();
+                fuzzy_filter(&names, &app.filter)
+            }
+        },
+        Selection::TAGS => {
+            if app.filter.is_empty() {
+                (0..app.tags.items.len()).rev().collect()
+            } else {
+                fuzzy_filter(&app.tags.items, &app.filter)
+            }
+        },
+        Selection::BRANCHES => {
+            if app.filter.is_empty() {
+                (0..app.branches.items.len()).rev().collect()
+            } else {
+                fuzzy_filter(&app.branches.items, &app.filter)
+            }
+        },
+        _ => Vec::new()
+    };
+
+    // Files & folders, narrowed by the active fuzzy filter when repositories are selected
+    let repository_indices = if app.selection == Selection::REPOSITORIES {
+        app.filtered_indices.clone()
+    } else {
+        (0..app.repositories.items.len()).collect()
+    };
+
+    let items: Vec<ListItem> = repository_indices
         .iter()
+        .filter_map(|&i| app.repositories.items.get(i))
         .map(|i| {
             convert_alfred_repository_to_list_item(i, &main_chunks[0])
         })
@@ -95,33 +186,128 @@ fn ui<'a, B: Backend>(f: &'a mut Frame<B>, app: &'a mut App) {
         .highlight_symbol("> ");
     f.render_stateful_widget(items, main_chunks[0], &mut app.repositories.state);
 
-    // Info at the bottom
-    let paragraph = Paragraph::new(format!("{}",  app.selected_repository_path))
-        .style(Style::default().bg(Color::White).fg(Color::Black))
-        .block(create_block())
-        .alignment(Alignment::Left);
+    // Commit activity sparkline for the highlighted repository
+    let activity = get_commit_activity(&app.repository, chunks[1].width as usize);
+    let sparkline = Sparkline::default()
+        .block(create_block().title("Activity"))
+        .data(&activity)
+        .style(Style::default().fg(Color::Green));
+    f.render_widget(sparkline, chunks[1]);
 
-    f.render_widget(paragraph, chunks[1]);
+    // Info at the bottom, replaced by a fetch gauge or the last checkout error
+    if let Some(ratio) = app.fetch_progress {
+        let gauge = Gauge::default()
+            .block(create_block().title("Fetching origin"))
+            .gauge_style(Style::default().fg(Color::Green))
+            .ratio(ratio.clamp(0.0, 1.0) as f64);
+        f.render_widget(gauge, chunks[2]);
+    } else {
+        let info_text = if app.filter_active || !app.filter.is_empty() {
+            let total = match app.selection {
+                Selection::REPOSITORIES => app.repositories.items.len(),
+                Selection::TAGS => app.tags.items.len(),
+                Selection::BRANCHES => app.branches.items.len(),
+                _ => 0
+            };
+            format!("/{} ({}/{})", app.filter, app.filtered_indices.len(), total)
+        } else {
+            match &app.last_error {
+                Some(err) => err.clone(),
+                None => app.selected_repository_path.clone()
+            }
+        };
+        let paragraph = Paragraph::new(info_text)
+            .style(Style::default().bg(Color::White).fg(Color::Black))
+            .block(create_block())
+            .alignment(Alignment::Left);
+
+        f.render_widget(paragraph, chunks[2]);
+    }
 
-    //Branches and Tags screens
+    //Branches, Tags and ref detail screens
     let right_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints(
             [
-                Constraint::Percentage(50),
-                Constraint::Percentage(50)
+                Constraint::Percentage(40),
+                Constraint::Percentage(40),
+                Constraint::Percentage(20)
             ]
         )
         .split(main_chunks[1]);
 
-    // Tags
-
-    let tag_list = create_selection_list(&app.tags.items, create_block_with_title(&app, Selection::TAGS));
+    // Tags, narrowed by the active fuzzy filter when tags are selected
+    let tag_list = if app.selection == Selection::TAGS {
+        create_filtered_list(&app.tags.items, &app.filtered_indices, create_block_with_title(&app, Selection::TAGS))
+    } else {
+        create_selection_list(&app.tags.items, create_block_with_title(&app, Selection::TAGS))
+    };
     f.render_stateful_widget(tag_list, right_chunks[0], &mut app.tags.state);
 
-    // Branches
-    let branch_list = create_selection_list(&app.branches.items, create_block_with_title(&app, Selection::BRANCHES));
+    // Branches, narrowed by the active fuzzy filter when branches are selected
+    let branch_list = if app.selection == Selection::BRANCHES {
+        create_filtered_list(&app.branches.items, &app.filtered_indices, create_block_with_title(&app, Selection::BRANCHES))
+    } else {
+        create_selection_list(&app.branches.items, create_block_with_title(&app, Selection::BRANCHES))
+    };
     f.render_stateful_widget(branch_list, right_chunks[1], &mut app.branches.state);
+
+    // Ref details for the highlighted tag or branch, resolved through filtered_indices
+    let highlighted_ref = match app.selection {
+        Selection::TAGS => get_selected_item(&app.tags.items, &app.filtered_indices, app.tags.state.selected()),
+        Selection::BRANCHES => get_selected_item(&app.branches.items, &app.filtered_indices, app.branches.state.selected()),
+        _ => None
+    };
+
+    // Ref details, cached per highlighted ref so revparse_single/peel_to_commit don't run every redraw
+    let detail_text = match highlighted_ref {
+        Some(ref_name) => {
+            if app.ref_details_cache_key.as_ref() != Some(&ref_name) {
+                app.ref_details_cache = get_ref_details(&app.repository, &ref_name);
+                app.ref_details_cache_key = Some(ref_name);
+            }
+            let details = &app.ref_details_cache;
+            format!("{} {}\n{}\n{}", details.short_hash, details.author, details.date, details.summary)
+        },
+        None => String::new()
+    };
+
+    let detail_paragraph = Paragraph::new(detail_text)
+        .block(create_block().borders(Borders::ALL).title("Details"))
+        .alignment(Alignment::Left);
+    f.render_widget(detail_paragraph, right_chunks[2]);
+
+    // Blame, cached per selected repository/file so it isn't redone on every redraw
+    let blame_file = get_default_blame_file(&app.repository).unwrap_or_else(|| PathBuf::from("README.md"));
+    let blame_key = (app.selected_repository_path.clone(), blame_file.clone());
+    if app.blame_cache_key.as_ref() != Some(&blame_key) {
+        app.blame_cache = get_file_blame(&app.repository, &blame_file);
+        app.blame_cache_key = Some(blame_key);
+    }
+
+    let blame_items: Vec<ListItem> = app.blame_cache
+        .iter()
+        .map(|(author, line)| convert_blame_line_to_list_item(author, line))
+        .collect();
+
+    let blame_list = List::new(blame_items)
+        .block(create_block_with_title(&app, Selection::BLAME));
+    f.render_widget(blame_list, main_chunks[2]);
+}
+
+fn get_selected_item(items: &Vec<String>, indices: &Vec<usize>, selected: Option<usize>) -> Option<String> {
+    let index = selected?;
+    let real_index = *indices.get(index)?;
+    items.get(real_index).cloned()
+}
+
+fn convert_blame_line_to_list_item<'a>(author: &'a Option<String>, line: &'a str) -> ListItem<'a> {
+    let gutter = author.clone().unwrap_or_else(|| "".to_string());
+    ListItem::new(Spans::from(vec![
+        Span::styled(format!("{:<20}", gutter), Style::default().fg(Color::DarkGray)),
+        Span::raw(" "),
+        Span::raw(line)
+    ]))
 }
 
 fn convert_alfred_repository_to_list_item<'a>(item: &'a AlfredRepository, chunk: &'a Rect) -> ListItem<'a> {
@@ -140,6 +326,21 @@ fn convert_alfred_repository_to_list_item<'a>(item: &'a AlfredRepository, chunk:
     ListItem::new(lines).style(Style::default().fg(Color::White).bg(line_color))
 }
 
+fn create_filtered_list<'a, T: Display>(items: &'a Vec<T>, indices: &Vec<usize>, b: Block<'a>) -> List<'a> {
+    let list_items: Vec<ListItem> = indices.iter()
+        .filter_map(|&i| items.get(i))
+        .map(|item| ListItem::new(Spans::from(vec![Span::raw(format!("{}", item))])))
+        .collect();
+
+    List::new(list_items)
+        .block(b)
+        .start_corner(Corner::TopLeft)
+        .highlight_style(
+            Style::default().add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ")
+}
+
 fn create_selection_list<'a, T: Display>(v: &'a Vec<T>, b: Block<'a>) -> List<'a > {
     List::new(convert_to_list_item(v))
         .block(b)
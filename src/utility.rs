@@ -1,6 +1,10 @@
 use std::fmt::Display;
-use std::path::PathBuf;
-use git2::{Repository};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use chrono::{TimeZone, Utc};
+use git2::{FetchOptions, RemoteCallbacks, Repository};
 use tui::style::{Color, Modifier, Style};
 use tui::text::{Span, Spans};
 use tui::widgets::{Block, Borders, ListItem};
@@ -60,6 +64,226 @@ pub fn get_repository_active_branch(repository: &Option<Repository>) -> String {
     branch_id
 }
 
+#[derive(Default)]
+pub struct RefDetails {
+    pub short_hash: String,
+    pub author: String,
+    pub date: String,
+    pub summary: String
+}
+
+pub fn get_ref_details(repository: &Option<Repository>, ref_name: &str) -> RefDetails {
+    let repo = match repository {
+        Some(repo) => repo,
+        None => return RefDetails::default()
+    };
+
+    let object = match repo.revparse_single(ref_name) {
+        Ok(object) => object,
+        Err(_) => return RefDetails::default()
+    };
+
+    let commit = match object.peel_to_commit() {
+        Ok(commit) => commit,
+        Err(_) => return RefDetails::default()
+    };
+
+    let date = Utc.timestamp_opt(commit.time().seconds(), 0)
+        .single()
+        .map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default();
+
+    RefDetails {
+        short_hash: commit.id().to_string()[..7].to_string(),
+        author: commit.author().name().unwrap_or("unknown").to_string(),
+        date,
+        summary: commit.summary().unwrap_or("").to_string()
+    }
+}
+
+pub fn get_commit_activity(repository: &Option<Repository>, buckets: usize) -> Vec<u64> {
+    if buckets == 0 {
+        return Vec::new();
+    }
+
+    let mut counts = vec![0u64; buckets];
+
+    let repo = match repository {
+        Some(repo) => repo,
+        None => return Vec::new()
+    };
+
+    let mut revwalk = match repo.revwalk() {
+        Ok(revwalk) => revwalk,
+        Err(_) => return Vec::new()
+    };
+
+    if revwalk.push_head().is_err() {
+        return Vec::new();
+    }
+
+    let times: Vec<i64> = revwalk
+        .filter_map(|oid| oid.ok())
+        .filter_map(|oid| repo.find_commit(oid).ok())
+        .map(|commit| commit.time().seconds())
+        .collect();
+
+    let oldest = match times.iter().min() {
+        Some(oldest) => *oldest,
+        None => return Vec::new()
+    };
+    let newest = match times.iter().max() {
+        Some(newest) => *newest,
+        None => return Vec::new()
+    };
+
+    let span = (newest - oldest).max(1) as f64;
+    for time in times {
+        let offset = (time - oldest) as f64 / span;
+        let bucket = ((offset * buckets as f64) as usize).min(buckets - 1);
+        counts[bucket] += 1;
+    }
+
+    counts
+}
+
+pub fn fetch_origin_with_progress(repo_path: PathBuf, progress: Sender<f32>) -> Result<(), String> {
+    let repo = Repository::open(&repo_path).map_err(|e| e.message().to_string())?;
+    let mut remote = repo.find_remote("origin").map_err(|e| e.message().to_string())?;
+
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.transfer_progress(|stats| {
+        let ratio = if stats.total_objects() > 0 {
+            stats.received_objects() as f32 / stats.total_objects() as f32
+        } else {
+            0.0
+        };
+        progress.send(ratio).is_ok()
+    });
+
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(callbacks);
+
+    remote.fetch(&[] as &[&str], Some(&mut fetch_options), None)
+        .map_err(|e| e.message().to_string())
+}
+
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let query_lower = query.to_lowercase();
+    let candidate_lower = candidate.to_lowercase();
+
+    let mut query_chars = query_lower.chars().peekable();
+    let mut score = 0i64;
+    let mut consecutive = 0i64;
+
+    for (i, c) in candidate_lower.chars().enumerate() {
+        let next_query_char = match query_chars.peek() {
+            Some(&q) => q,
+            None => break
+        };
+
+        if c == next_query_char {
+            query_chars.next();
+            consecutive += 1;
+            score += consecutive * 2 - i as i64;
+        } else {
+            consecutive = 0;
+        }
+    }
+
+    if query_chars.peek().is_none() {
+        Some(score)
+    } else {
+        None
+    }
+}
+
+pub fn fuzzy_filter(items: &Vec<String>, query: &str) -> Vec<usize> {
+    if query.is_empty() {
+        return (0..items.len()).collect();
+    }
+
+    let mut scored: Vec<(usize, i64)> = items.iter()
+        .enumerate()
+        .filter_map(|(i, item)| fuzzy_match(query, item).map(|score| (i, score)))
+        .collect();
+
+    scored.sort_by(|a, b| b.1.cmp(&a.1));
+    scored.into_iter().map(|(i, _)| i).collect()
+}
+
+pub fn get_default_blame_file(repository: &Option<Repository>) -> Option<PathBuf> {
+    let repo = repository.as_ref()?;
+    let index = repo.index().ok()?;
+
+    let preferred = PathBuf::from("README.md");
+    let mut first_entry: Option<PathBuf> = None;
+
+    for entry in index.iter() {
+        let path = PathBuf::from(String::from_utf8_lossy(&entry.path).to_string());
+        if path == preferred {
+            return Some(preferred);
+        }
+        if first_entry.is_none() {
+            first_entry = Some(path);
+        }
+    }
+
+    first_entry
+}
+
+pub fn get_file_blame(repository: &Option<Repository>, file_path: &Path) -> Vec<(Option<String>, String)> {
+    let mut result = Vec::new();
+
+    let repo = match repository {
+        Some(repo) => repo,
+        None => return result
+    };
+
+    let blame = match repo.blame_file(file_path, None) {
+        Ok(blame) => blame,
+        Err(_) => return result
+    };
+
+    let full_path = match repo.workdir() {
+        Some(workdir) => workdir.join(file_path),
+        None => return result
+    };
+
+    let file = match File::open(&full_path) {
+        Ok(file) => file,
+        Err(_) => return result
+    };
+
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => continue
+        };
+
+        let line_number = i + 1;
+        let author = blame.iter().find_map(|hunk| {
+            let start = hunk.final_start_line();
+            let end = start + hunk.lines_in_hunk();
+            if line_number >= start && line_number < end {
+                let short_hash = hunk.final_commit_id().to_string()[..7].to_string();
+                let author_name = hunk.final_signature().name().unwrap_or("unknown").to_string();
+                Some(format!("{} {}", short_hash, author_name))
+            } else {
+                None
+            }
+        });
+
+        result.push((author, line));
+    }
+
+    result
+}
+
 pub fn convert_to_list_item<T: Display>(iterator: &Vec<T>) -> Vec<ListItem<'static>> {
     iterator.iter()
         .rev()
@@ -91,4 +315,90 @@ pub fn create_block_with_title(app: &App, selection: Selection) -> Block<'static
 pub fn create_block() -> Block<'static> {
     let b = Block::default();
     b.borders(Borders::NONE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use git2::{Signature, Time};
+
+    fn init_temp_repo(name: &str) -> (PathBuf, Repository) {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!("gitten-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let repo = Repository::init(&dir).unwrap();
+        (dir, repo)
+    }
+
+    fn commit_at(repo: &Repository, seconds: i64, message: &str) {
+        let signature = Signature::new("Test", "test@example.com", &Time::new(seconds, 0)).unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+
+        let parent = repo.head().and_then(|head| head.peel_to_commit());
+        let parents: Vec<git2::Commit> = parent.into_iter().collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+
+        repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parent_refs).unwrap();
+    }
+
+    #[test]
+    fn get_commit_activity_returns_empty_for_zero_buckets() {
+        let (dir, repo) = init_temp_repo("zero-buckets");
+        commit_at(&repo, 1_700_000_000, "initial");
+
+        let activity = get_commit_activity(&Some(repo), 0);
+        assert!(activity.is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn get_commit_activity_bins_commits_by_time() {
+        let (dir, repo) = init_temp_repo("bins");
+        commit_at(&repo, 1_700_000_000, "first");
+        commit_at(&repo, 1_700_000_100, "second");
+
+        let activity = get_commit_activity(&Some(repo), 4);
+        assert_eq!(activity.len(), 4);
+        assert_eq!(activity.iter().sum::<u64>(), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn get_commit_activity_returns_empty_without_a_repository() {
+        assert!(get_commit_activity(&None, 8).is_empty());
+    }
+
+    #[test]
+    fn fuzzy_match_accepts_in_order_subsequence_case_insensitively() {
+        assert!(fuzzy_match("mn", "Main").is_some());
+        assert!(fuzzy_match("MN", "main").is_some());
+    }
+
+    #[test]
+    fn fuzzy_match_rejects_out_of_order_or_missing_characters() {
+        assert!(fuzzy_match("nm", "main").is_none());
+        assert!(fuzzy_match("xyz", "main").is_none());
+    }
+
+    #[test]
+    fn fuzzy_match_empty_query_matches_everything() {
+        assert_eq!(fuzzy_match("", "anything"), Some(0));
+    }
+
+    #[test]
+    fn fuzzy_filter_sorts_closer_and_earlier_matches_first() {
+        let items = vec!["release".to_string(), "main".to_string(), "feature/main".to_string()];
+        assert_eq!(fuzzy_filter(&items, "main"), vec![1, 2]);
+    }
+
+    #[test]
+    fn fuzzy_filter_empty_query_keeps_original_order() {
+        let items = vec!["b".to_string(), "a".to_string()];
+        assert_eq!(fuzzy_filter(&items, ""), vec![0, 1]);
+    }
 }
\ No newline at end of file